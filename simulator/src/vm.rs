@@ -1,36 +1,2165 @@
 // Copyright 2025 Erst Users
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::HashMap;
+
+use wasm_encoder::{Function, Instruction, Module};
 use wasmparser::{Operator, Parser, Payload};
 
+/// Thin wrapper over [`check`] with [`Policy::strict`], kept for backward
+/// compatibility: it reports only the first violation, formatted the same
+/// way this function has always reported its one and only check
+/// ("floating-point instructions are not allowed under strict Soroban
+/// compatibility"). Callers that want the full report, or a less restrictive
+/// policy, should call [`check`] directly.
 pub fn enforce_soroban_compatibility(wasm: &[u8]) -> Result<(), String> {
+    check(wasm, &Policy::strict()).map_err(|violations| {
+        let first = &violations[0];
+        match &first.message {
+            Some(message) => message.clone(),
+            None => format!(
+                "{} are not allowed under strict Soroban compatibility",
+                first.category.description()
+            ),
+        }
+    })
+}
+
+/// Returns `true` for any opcode that touches an `f32`, `f64`, or `v128`
+/// float lane, whether it produces, consumes, or converts one.
+///
+/// Floating-point behavior shows up in WASM in three shapes, all of which
+/// need to be rejected for strict Soroban compatibility:
+///
+/// 1. Native `f32`/`f64` ops (arithmetic, comparisons, loads/stores, consts).
+/// 2. Scalar conversions that bridge ints and floats (`*ConvertI*`,
+///    `I*TruncF*`, `I*TruncSatF*`, `F*DemoteF*`/`F*PromoteF*`, and the
+///    bit-reinterpreting casts).
+/// 3. 128-bit SIMD float lanes (`F32x4*`/`F64x2*`), their int-bridging
+///    conversions, and the relaxed-SIMD rounding/truncation variants.
+///
+/// This is matched explicitly, opcode by opcode, rather than via a
+/// string-prefix heuristic, and is kept in sync by hand against
+/// `wasmparser`'s operator list: a new float-touching opcode added upstream
+/// still needs a line added here, since an unmatched `Operator` variant
+/// just evaluates to `false` rather than failing to compile.
+fn is_float_op(op: &Operator) -> bool {
+    matches!(
+        op,
+        // --- native f32/f64 ops ---
+        Operator::F32Load { .. }
+            | Operator::F64Load { .. }
+            | Operator::F32Store { .. }
+            | Operator::F64Store { .. }
+            | Operator::F32Const { .. }
+            | Operator::F64Const { .. }
+            | Operator::F32Eq
+            | Operator::F32Ne
+            | Operator::F32Lt
+            | Operator::F32Gt
+            | Operator::F32Le
+            | Operator::F32Ge
+            | Operator::F64Eq
+            | Operator::F64Ne
+            | Operator::F64Lt
+            | Operator::F64Gt
+            | Operator::F64Le
+            | Operator::F64Ge
+            | Operator::F32Abs
+            | Operator::F32Neg
+            | Operator::F32Ceil
+            | Operator::F32Floor
+            | Operator::F32Trunc
+            | Operator::F32Nearest
+            | Operator::F32Sqrt
+            | Operator::F32Add
+            | Operator::F32Sub
+            | Operator::F32Mul
+            | Operator::F32Div
+            | Operator::F32Min
+            | Operator::F32Max
+            | Operator::F32Copysign
+            | Operator::F64Abs
+            | Operator::F64Neg
+            | Operator::F64Ceil
+            | Operator::F64Floor
+            | Operator::F64Trunc
+            | Operator::F64Nearest
+            | Operator::F64Sqrt
+            | Operator::F64Add
+            | Operator::F64Sub
+            | Operator::F64Mul
+            | Operator::F64Div
+            | Operator::F64Min
+            | Operator::F64Max
+            | Operator::F64Copysign
+            // --- scalar int<->float conversions ---
+            | Operator::I32TruncF32S
+            | Operator::I32TruncF32U
+            | Operator::I32TruncF64S
+            | Operator::I32TruncF64U
+            | Operator::I64TruncF32S
+            | Operator::I64TruncF32U
+            | Operator::I64TruncF64S
+            | Operator::I64TruncF64U
+            | Operator::I32TruncSatF32S
+            | Operator::I32TruncSatF32U
+            | Operator::I32TruncSatF64S
+            | Operator::I32TruncSatF64U
+            | Operator::I64TruncSatF32S
+            | Operator::I64TruncSatF32U
+            | Operator::I64TruncSatF64S
+            | Operator::I64TruncSatF64U
+            | Operator::F32ConvertI32S
+            | Operator::F32ConvertI32U
+            | Operator::F32ConvertI64S
+            | Operator::F32ConvertI64U
+            | Operator::F64ConvertI32S
+            | Operator::F64ConvertI32U
+            | Operator::F64ConvertI64S
+            | Operator::F64ConvertI64U
+            | Operator::F32DemoteF64
+            | Operator::F64PromoteF32
+            | Operator::I32ReinterpretF32
+            | Operator::I64ReinterpretF64
+            | Operator::F32ReinterpretI32
+            | Operator::F64ReinterpretI64
+            // --- SIMD float lanes ---
+            | Operator::F32x4Splat
+            | Operator::F32x4ExtractLane { .. }
+            | Operator::F32x4ReplaceLane { .. }
+            | Operator::F32x4Eq
+            | Operator::F32x4Ne
+            | Operator::F32x4Lt
+            | Operator::F32x4Gt
+            | Operator::F32x4Le
+            | Operator::F32x4Ge
+            | Operator::F32x4Ceil
+            | Operator::F32x4Floor
+            | Operator::F32x4Trunc
+            | Operator::F32x4Nearest
+            | Operator::F32x4Abs
+            | Operator::F32x4Neg
+            | Operator::F32x4Sqrt
+            | Operator::F32x4Add
+            | Operator::F32x4Sub
+            | Operator::F32x4Mul
+            | Operator::F32x4Div
+            | Operator::F32x4Min
+            | Operator::F32x4Max
+            | Operator::F32x4PMin
+            | Operator::F32x4PMax
+            | Operator::F64x2Splat
+            | Operator::F64x2ExtractLane { .. }
+            | Operator::F64x2ReplaceLane { .. }
+            | Operator::F64x2Eq
+            | Operator::F64x2Ne
+            | Operator::F64x2Lt
+            | Operator::F64x2Gt
+            | Operator::F64x2Le
+            | Operator::F64x2Ge
+            | Operator::F64x2Ceil
+            | Operator::F64x2Floor
+            | Operator::F64x2Trunc
+            | Operator::F64x2Nearest
+            | Operator::F64x2Abs
+            | Operator::F64x2Neg
+            | Operator::F64x2Sqrt
+            | Operator::F64x2Add
+            | Operator::F64x2Sub
+            | Operator::F64x2Mul
+            | Operator::F64x2Div
+            | Operator::F64x2Min
+            | Operator::F64x2Max
+            | Operator::F64x2PMin
+            | Operator::F64x2PMax
+            // --- SIMD int<->float conversions ---
+            | Operator::F32x4ConvertI32x4S
+            | Operator::F32x4ConvertI32x4U
+            | Operator::F64x2ConvertLowI32x4S
+            | Operator::F64x2ConvertLowI32x4U
+            | Operator::I32x4TruncSatF32x4S
+            | Operator::I32x4TruncSatF32x4U
+            | Operator::I32x4TruncSatF64x2SZero
+            | Operator::I32x4TruncSatF64x2UZero
+            | Operator::F32x4DemoteF64x2Zero
+            | Operator::F64x2PromoteLowF32x4
+            // --- relaxed-SIMD float rounding/truncation ---
+            | Operator::I32x4RelaxedTruncF32x4S
+            | Operator::I32x4RelaxedTruncF32x4U
+            | Operator::I32x4RelaxedTruncF64x2SZero
+            | Operator::I32x4RelaxedTruncF64x2UZero
+            | Operator::F32x4RelaxedMadd
+            | Operator::F32x4RelaxedNmadd
+            | Operator::F64x2RelaxedMadd
+            | Operator::F64x2RelaxedNmadd
+            | Operator::F32x4RelaxedMin
+            | Operator::F32x4RelaxedMax
+            | Operator::F64x2RelaxedMin
+            | Operator::F64x2RelaxedMax
+    )
+}
+
+/// Returns `true` for any opcode belonging to the 128-bit SIMD (`v128`)
+/// proposal, including the relaxed-SIMD extension, whether the lanes it
+/// operates on are integer or floating-point.
+///
+/// Matched explicitly against every `@simd`/`@relaxed_simd` `Operator`
+/// variant, the same way [`is_float_op`] is, rather than via a name-prefix
+/// heuristic: a prefix/substring check over `{op:?}` misses pure-integer
+/// lane ops whose name has no `x4`/`x2` substring (e.g. `I8x16Add`,
+/// `I16x8Mul`), silently under-reporting SIMD usage.
+fn is_simd_op(op: &Operator) -> bool {
+    matches!(
+        op,
+        // --- loads, stores, and the v128 constant ---
+        Operator::V128Load { .. }
+            | Operator::V128Load8x8S { .. }
+            | Operator::V128Load8x8U { .. }
+            | Operator::V128Load16x4S { .. }
+            | Operator::V128Load16x4U { .. }
+            | Operator::V128Load32x2S { .. }
+            | Operator::V128Load32x2U { .. }
+            | Operator::V128Load8Splat { .. }
+            | Operator::V128Load16Splat { .. }
+            | Operator::V128Load32Splat { .. }
+            | Operator::V128Load64Splat { .. }
+            | Operator::V128Load32Zero { .. }
+            | Operator::V128Load64Zero { .. }
+            | Operator::V128Store { .. }
+            | Operator::V128Load8Lane { .. }
+            | Operator::V128Load16Lane { .. }
+            | Operator::V128Load32Lane { .. }
+            | Operator::V128Load64Lane { .. }
+            | Operator::V128Store8Lane { .. }
+            | Operator::V128Store16Lane { .. }
+            | Operator::V128Store32Lane { .. }
+            | Operator::V128Store64Lane { .. }
+            | Operator::V128Const { .. }
+        // --- lane shuffle/extract/replace ---
+            | Operator::I8x16Shuffle { .. }
+            | Operator::I8x16ExtractLaneS { .. }
+            | Operator::I8x16ExtractLaneU { .. }
+            | Operator::I8x16ReplaceLane { .. }
+            | Operator::I16x8ExtractLaneS { .. }
+            | Operator::I16x8ExtractLaneU { .. }
+            | Operator::I16x8ReplaceLane { .. }
+            | Operator::I32x4ExtractLane { .. }
+            | Operator::I32x4ReplaceLane { .. }
+            | Operator::I64x2ExtractLane { .. }
+            | Operator::I64x2ReplaceLane { .. }
+            | Operator::F32x4ExtractLane { .. }
+            | Operator::F32x4ReplaceLane { .. }
+            | Operator::F64x2ExtractLane { .. }
+            | Operator::F64x2ReplaceLane { .. }
+            | Operator::I8x16Swizzle
+        // --- splats ---
+            | Operator::I8x16Splat
+            | Operator::I16x8Splat
+            | Operator::I32x4Splat
+            | Operator::I64x2Splat
+            | Operator::F32x4Splat
+            | Operator::F64x2Splat
+        // --- lanewise comparisons ---
+            | Operator::I8x16Eq
+            | Operator::I8x16Ne
+            | Operator::I8x16LtS
+            | Operator::I8x16LtU
+            | Operator::I8x16GtS
+            | Operator::I8x16GtU
+            | Operator::I8x16LeS
+            | Operator::I8x16LeU
+            | Operator::I8x16GeS
+            | Operator::I8x16GeU
+            | Operator::I16x8Eq
+            | Operator::I16x8Ne
+            | Operator::I16x8LtS
+            | Operator::I16x8LtU
+            | Operator::I16x8GtS
+            | Operator::I16x8GtU
+            | Operator::I16x8LeS
+            | Operator::I16x8LeU
+            | Operator::I16x8GeS
+            | Operator::I16x8GeU
+            | Operator::I32x4Eq
+            | Operator::I32x4Ne
+            | Operator::I32x4LtS
+            | Operator::I32x4LtU
+            | Operator::I32x4GtS
+            | Operator::I32x4GtU
+            | Operator::I32x4LeS
+            | Operator::I32x4LeU
+            | Operator::I32x4GeS
+            | Operator::I32x4GeU
+            | Operator::I64x2Eq
+            | Operator::I64x2Ne
+            | Operator::I64x2LtS
+            | Operator::I64x2GtS
+            | Operator::I64x2LeS
+            | Operator::I64x2GeS
+            | Operator::F32x4Eq
+            | Operator::F32x4Ne
+            | Operator::F32x4Lt
+            | Operator::F32x4Gt
+            | Operator::F32x4Le
+            | Operator::F32x4Ge
+            | Operator::F64x2Eq
+            | Operator::F64x2Ne
+            | Operator::F64x2Lt
+            | Operator::F64x2Gt
+            | Operator::F64x2Le
+            | Operator::F64x2Ge
+        // --- whole-vector bitwise ops ---
+            | Operator::V128Not
+            | Operator::V128And
+            | Operator::V128AndNot
+            | Operator::V128Or
+            | Operator::V128Xor
+            | Operator::V128Bitselect
+            | Operator::V128AnyTrue
+        // --- integer lane arithmetic ---
+            | Operator::I8x16Abs
+            | Operator::I8x16Neg
+            | Operator::I8x16Popcnt
+            | Operator::I8x16AllTrue
+            | Operator::I8x16Bitmask
+            | Operator::I8x16NarrowI16x8S
+            | Operator::I8x16NarrowI16x8U
+            | Operator::I8x16Shl
+            | Operator::I8x16ShrS
+            | Operator::I8x16ShrU
+            | Operator::I8x16Add
+            | Operator::I8x16AddSatS
+            | Operator::I8x16AddSatU
+            | Operator::I8x16Sub
+            | Operator::I8x16SubSatS
+            | Operator::I8x16SubSatU
+            | Operator::I8x16MinS
+            | Operator::I8x16MinU
+            | Operator::I8x16MaxS
+            | Operator::I8x16MaxU
+            | Operator::I8x16AvgrU
+            | Operator::I16x8ExtAddPairwiseI8x16S
+            | Operator::I16x8ExtAddPairwiseI8x16U
+            | Operator::I16x8Abs
+            | Operator::I16x8Neg
+            | Operator::I16x8Q15MulrSatS
+            | Operator::I16x8AllTrue
+            | Operator::I16x8Bitmask
+            | Operator::I16x8NarrowI32x4S
+            | Operator::I16x8NarrowI32x4U
+            | Operator::I16x8ExtendLowI8x16S
+            | Operator::I16x8ExtendHighI8x16S
+            | Operator::I16x8ExtendLowI8x16U
+            | Operator::I16x8ExtendHighI8x16U
+            | Operator::I16x8Shl
+            | Operator::I16x8ShrS
+            | Operator::I16x8ShrU
+            | Operator::I16x8Add
+            | Operator::I16x8AddSatS
+            | Operator::I16x8AddSatU
+            | Operator::I16x8Sub
+            | Operator::I16x8SubSatS
+            | Operator::I16x8SubSatU
+            | Operator::I16x8Mul
+            | Operator::I16x8MinS
+            | Operator::I16x8MinU
+            | Operator::I16x8MaxS
+            | Operator::I16x8MaxU
+            | Operator::I16x8AvgrU
+            | Operator::I16x8ExtMulLowI8x16S
+            | Operator::I16x8ExtMulHighI8x16S
+            | Operator::I16x8ExtMulLowI8x16U
+            | Operator::I16x8ExtMulHighI8x16U
+            | Operator::I32x4ExtAddPairwiseI16x8S
+            | Operator::I32x4ExtAddPairwiseI16x8U
+            | Operator::I32x4Abs
+            | Operator::I32x4Neg
+            | Operator::I32x4AllTrue
+            | Operator::I32x4Bitmask
+            | Operator::I32x4ExtendLowI16x8S
+            | Operator::I32x4ExtendHighI16x8S
+            | Operator::I32x4ExtendLowI16x8U
+            | Operator::I32x4ExtendHighI16x8U
+            | Operator::I32x4Shl
+            | Operator::I32x4ShrS
+            | Operator::I32x4ShrU
+            | Operator::I32x4Add
+            | Operator::I32x4Sub
+            | Operator::I32x4Mul
+            | Operator::I32x4MinS
+            | Operator::I32x4MinU
+            | Operator::I32x4MaxS
+            | Operator::I32x4MaxU
+            | Operator::I32x4DotI16x8S
+            | Operator::I32x4ExtMulLowI16x8S
+            | Operator::I32x4ExtMulHighI16x8S
+            | Operator::I32x4ExtMulLowI16x8U
+            | Operator::I32x4ExtMulHighI16x8U
+            | Operator::I64x2Abs
+            | Operator::I64x2Neg
+            | Operator::I64x2AllTrue
+            | Operator::I64x2Bitmask
+            | Operator::I64x2ExtendLowI32x4S
+            | Operator::I64x2ExtendHighI32x4S
+            | Operator::I64x2ExtendLowI32x4U
+            | Operator::I64x2ExtendHighI32x4U
+            | Operator::I64x2Shl
+            | Operator::I64x2ShrS
+            | Operator::I64x2ShrU
+            | Operator::I64x2Add
+            | Operator::I64x2Sub
+            | Operator::I64x2Mul
+            | Operator::I64x2ExtMulLowI32x4S
+            | Operator::I64x2ExtMulHighI32x4S
+            | Operator::I64x2ExtMulLowI32x4U
+            | Operator::I64x2ExtMulHighI32x4U
+        // --- float lane arithmetic ---
+            | Operator::F32x4Ceil
+            | Operator::F32x4Floor
+            | Operator::F32x4Trunc
+            | Operator::F32x4Nearest
+            | Operator::F32x4Abs
+            | Operator::F32x4Neg
+            | Operator::F32x4Sqrt
+            | Operator::F32x4Add
+            | Operator::F32x4Sub
+            | Operator::F32x4Mul
+            | Operator::F32x4Div
+            | Operator::F32x4Min
+            | Operator::F32x4Max
+            | Operator::F32x4PMin
+            | Operator::F32x4PMax
+            | Operator::F64x2Ceil
+            | Operator::F64x2Floor
+            | Operator::F64x2Trunc
+            | Operator::F64x2Nearest
+            | Operator::F64x2Abs
+            | Operator::F64x2Neg
+            | Operator::F64x2Sqrt
+            | Operator::F64x2Add
+            | Operator::F64x2Sub
+            | Operator::F64x2Mul
+            | Operator::F64x2Div
+            | Operator::F64x2Min
+            | Operator::F64x2Max
+            | Operator::F64x2PMin
+            | Operator::F64x2PMax
+        // --- SIMD int<->float conversions ---
+            | Operator::I32x4TruncSatF32x4S
+            | Operator::I32x4TruncSatF32x4U
+            | Operator::F32x4ConvertI32x4S
+            | Operator::F32x4ConvertI32x4U
+            | Operator::I32x4TruncSatF64x2SZero
+            | Operator::I32x4TruncSatF64x2UZero
+            | Operator::F64x2ConvertLowI32x4S
+            | Operator::F64x2ConvertLowI32x4U
+            | Operator::F32x4DemoteF64x2Zero
+            | Operator::F64x2PromoteLowF32x4
+        // --- relaxed-SIMD variants ---
+            | Operator::I8x16RelaxedSwizzle
+            | Operator::I32x4RelaxedTruncF32x4S
+            | Operator::I32x4RelaxedTruncF32x4U
+            | Operator::I32x4RelaxedTruncF64x2SZero
+            | Operator::I32x4RelaxedTruncF64x2UZero
+            | Operator::F32x4RelaxedMadd
+            | Operator::F32x4RelaxedNmadd
+            | Operator::F64x2RelaxedMadd
+            | Operator::F64x2RelaxedNmadd
+            | Operator::I8x16RelaxedLaneselect
+            | Operator::I16x8RelaxedLaneselect
+            | Operator::I32x4RelaxedLaneselect
+            | Operator::I64x2RelaxedLaneselect
+            | Operator::F32x4RelaxedMin
+            | Operator::F32x4RelaxedMax
+            | Operator::F64x2RelaxedMin
+            | Operator::F64x2RelaxedMax
+            | Operator::I16x8RelaxedQ15mulrS
+            | Operator::I16x8RelaxedDotI8x16I7x16S
+            | Operator::I32x4RelaxedDotI8x16I7x16AddS
+    )
+}
+
+/// Name of the module that soft-float helper functions are imported from in
+/// a [`lower_soft_float`]-rewritten module.
+const SOFT_FLOAT_IMPORT_MODULE: &str = "softfloat";
+
+/// One software-floating-point helper: a name to import it under, and its
+/// signature in the all-integer domain that [`lower_soft_float`] rewrites
+/// everything into (`f32` becomes `i32` bits, `f64` becomes `i64` bits).
+struct SoftFloatHelper {
+    name: &'static str,
+    params: &'static [wasm_encoder::ValType],
+    results: &'static [wasm_encoder::ValType],
+}
+
+use wasm_encoder::ValType as EncValType;
+
+const I32: EncValType = EncValType::I32;
+const I64: EncValType = EncValType::I64;
+
+/// How a single float-touching `Operator` is lowered by [`lower_soft_float`].
+enum Lowering {
+    /// Replace with `i32.const`/`i64.const` carrying the same bit pattern.
+    ConstI32(i32),
+    ConstI64(i64),
+    /// Bit-reinterpreting casts are a no-op once floats are already
+    /// represented as their raw bits: the value doesn't move.
+    Identity,
+    /// Loads/stores are byte-identical for floats and their bit-pattern
+    /// integers, so these become a plain int load/store with the same
+    /// `memarg`.
+    Load32(wasmparser::MemArg),
+    Load64(wasmparser::MemArg),
+    Store32(wasmparser::MemArg),
+    Store64(wasmparser::MemArg),
+    /// Everything else becomes a `call` to an imported software-float
+    /// routine operating on bit patterns.
+    Helper(&'static SoftFloatHelper),
+    /// Opcodes this pass doesn't know how to lower yet (currently: the v128
+    /// SIMD float lanes). Surfaced as an error rather than silently
+    /// producing a module with different semantics.
+    Unsupported,
+}
+
+macro_rules! helper {
+    ($name:ident, $params:expr, $results:expr) => {{
+        static H: SoftFloatHelper = SoftFloatHelper {
+            name: stringify!($name),
+            params: $params,
+            results: $results,
+        };
+        Lowering::Helper(&H)
+    }};
+}
+
+fn lower_operator(op: &Operator) -> Option<Lowering> {
+    use Operator as O;
+    Some(match *op {
+        O::F32Const { value } => Lowering::ConstI32(value.bits() as i32),
+        O::F64Const { value } => Lowering::ConstI64(value.bits() as i64),
+
+        O::I32ReinterpretF32
+        | O::F32ReinterpretI32
+        | O::I64ReinterpretF64
+        | O::F64ReinterpretI64 => Lowering::Identity,
+
+        O::F32Load { memarg } => Lowering::Load32(memarg),
+        O::F64Load { memarg } => Lowering::Load64(memarg),
+        O::F32Store { memarg } => Lowering::Store32(memarg),
+        O::F64Store { memarg } => Lowering::Store64(memarg),
+
+        O::F32Abs => helper!(f32_abs, &[I32], &[I32]),
+        O::F32Neg => helper!(f32_neg, &[I32], &[I32]),
+        O::F32Ceil => helper!(f32_ceil, &[I32], &[I32]),
+        O::F32Floor => helper!(f32_floor, &[I32], &[I32]),
+        O::F32Trunc => helper!(f32_trunc, &[I32], &[I32]),
+        O::F32Nearest => helper!(f32_nearest, &[I32], &[I32]),
+        O::F32Sqrt => helper!(f32_sqrt, &[I32], &[I32]),
+        O::F32Add => helper!(f32_add, &[I32, I32], &[I32]),
+        O::F32Sub => helper!(f32_sub, &[I32, I32], &[I32]),
+        O::F32Mul => helper!(f32_mul, &[I32, I32], &[I32]),
+        O::F32Div => helper!(f32_div, &[I32, I32], &[I32]),
+        O::F32Min => helper!(f32_min, &[I32, I32], &[I32]),
+        O::F32Max => helper!(f32_max, &[I32, I32], &[I32]),
+        O::F32Copysign => helper!(f32_copysign, &[I32, I32], &[I32]),
+        O::F32Eq => helper!(f32_eq, &[I32, I32], &[I32]),
+        O::F32Ne => helper!(f32_ne, &[I32, I32], &[I32]),
+        O::F32Lt => helper!(f32_lt, &[I32, I32], &[I32]),
+        O::F32Gt => helper!(f32_gt, &[I32, I32], &[I32]),
+        O::F32Le => helper!(f32_le, &[I32, I32], &[I32]),
+        O::F32Ge => helper!(f32_ge, &[I32, I32], &[I32]),
+
+        O::F64Abs => helper!(f64_abs, &[I64], &[I64]),
+        O::F64Neg => helper!(f64_neg, &[I64], &[I64]),
+        O::F64Ceil => helper!(f64_ceil, &[I64], &[I64]),
+        O::F64Floor => helper!(f64_floor, &[I64], &[I64]),
+        O::F64Trunc => helper!(f64_trunc, &[I64], &[I64]),
+        O::F64Nearest => helper!(f64_nearest, &[I64], &[I64]),
+        O::F64Sqrt => helper!(f64_sqrt, &[I64], &[I64]),
+        O::F64Add => helper!(f64_add, &[I64, I64], &[I64]),
+        O::F64Sub => helper!(f64_sub, &[I64, I64], &[I64]),
+        O::F64Mul => helper!(f64_mul, &[I64, I64], &[I64]),
+        O::F64Div => helper!(f64_div, &[I64, I64], &[I64]),
+        O::F64Min => helper!(f64_min, &[I64, I64], &[I64]),
+        O::F64Max => helper!(f64_max, &[I64, I64], &[I64]),
+        O::F64Copysign => helper!(f64_copysign, &[I64, I64], &[I64]),
+        O::F64Eq => helper!(f64_eq, &[I64, I64], &[I32]),
+        O::F64Ne => helper!(f64_ne, &[I64, I64], &[I32]),
+        O::F64Lt => helper!(f64_lt, &[I64, I64], &[I32]),
+        O::F64Gt => helper!(f64_gt, &[I64, I64], &[I32]),
+        O::F64Le => helper!(f64_le, &[I64, I64], &[I32]),
+        O::F64Ge => helper!(f64_ge, &[I64, I64], &[I32]),
+
+        O::I32TruncF32S => helper!(i32_trunc_f32_s, &[I32], &[I32]),
+        O::I32TruncF32U => helper!(i32_trunc_f32_u, &[I32], &[I32]),
+        O::I32TruncF64S => helper!(i32_trunc_f64_s, &[I64], &[I32]),
+        O::I32TruncF64U => helper!(i32_trunc_f64_u, &[I64], &[I32]),
+        O::I64TruncF32S => helper!(i64_trunc_f32_s, &[I32], &[I64]),
+        O::I64TruncF32U => helper!(i64_trunc_f32_u, &[I32], &[I64]),
+        O::I64TruncF64S => helper!(i64_trunc_f64_s, &[I64], &[I64]),
+        O::I64TruncF64U => helper!(i64_trunc_f64_u, &[I64], &[I64]),
+        O::I32TruncSatF32S => helper!(i32_trunc_sat_f32_s, &[I32], &[I32]),
+        O::I32TruncSatF32U => helper!(i32_trunc_sat_f32_u, &[I32], &[I32]),
+        O::I32TruncSatF64S => helper!(i32_trunc_sat_f64_s, &[I64], &[I32]),
+        O::I32TruncSatF64U => helper!(i32_trunc_sat_f64_u, &[I64], &[I32]),
+        O::I64TruncSatF32S => helper!(i64_trunc_sat_f32_s, &[I32], &[I64]),
+        O::I64TruncSatF32U => helper!(i64_trunc_sat_f32_u, &[I32], &[I64]),
+        O::I64TruncSatF64S => helper!(i64_trunc_sat_f64_s, &[I64], &[I64]),
+        O::I64TruncSatF64U => helper!(i64_trunc_sat_f64_u, &[I64], &[I64]),
+
+        O::F32ConvertI32S => helper!(f32_convert_i32_s, &[I32], &[I32]),
+        O::F32ConvertI32U => helper!(f32_convert_i32_u, &[I32], &[I32]),
+        O::F32ConvertI64S => helper!(f32_convert_i64_s, &[I64], &[I32]),
+        O::F32ConvertI64U => helper!(f32_convert_i64_u, &[I64], &[I32]),
+        O::F64ConvertI32S => helper!(f64_convert_i32_s, &[I32], &[I64]),
+        O::F64ConvertI32U => helper!(f64_convert_i32_u, &[I32], &[I64]),
+        O::F64ConvertI64S => helper!(f64_convert_i64_s, &[I64], &[I64]),
+        O::F64ConvertI64U => helper!(f64_convert_i64_u, &[I64], &[I64]),
+        O::F32DemoteF64 => helper!(f32_demote_f64, &[I64], &[I32]),
+        O::F64PromoteF32 => helper!(f64_promote_f32, &[I32], &[I64]),
+
+        _ if is_float_op(op) => Lowering::Unsupported,
+        _ => return None,
+    })
+}
+
+/// Rewrites every floating-point instruction in `wasm` into a call against
+/// an imported software-float routine that operates on the raw `i32`/`i64`
+/// bit pattern of the value instead, mirroring the hard-float/soft-float
+/// split used on targets without an FPU. Function signatures, locals, and
+/// globals that were `f32`/`f64` become `i32`/`i64` carrying those bits.
+///
+/// The resulting module is guaranteed to contain zero native float opcodes,
+/// so it clears the float check of [`enforce_soroban_compatibility`], while preserving
+/// observable arithmetic semantics (the caller is expected to link the
+/// `softfloat` import module against a real IEEE-754 bit-pattern
+/// implementation, e.g. Berkeley SoftFloat compiled to WASM).
+///
+/// Scalar `f32`/`f64` code is fully supported. Modules that use v128 SIMD
+/// float lanes, or that have a start section or function-referencing
+/// element segments, are rejected with an error rather than silently
+/// mis-lowered, since scalarizing those requires more surgery than this
+/// pass currently does.
+///
+/// Only floats are lowered: `return_call`, `ref.func`, `v128` params and
+/// locals, and shared memories are carried over unchanged, and the strict
+/// policy of [`enforce_soroban_compatibility`] still rejects a lowered module
+/// that contains any of them.
+pub fn lower_soft_float(wasm: &[u8]) -> Result<Vec<u8>, String> {
+    use wasmparser::{ExternalKind, TypeRef};
+
+    let mut types: Vec<wasmparser::FuncType> = Vec::new();
+    let mut imports: Vec<wasmparser::Import> = Vec::new();
+    let mut func_type_indices: Vec<u32> = Vec::new();
+    let mut code_bodies: Vec<wasmparser::FunctionBody> = Vec::new();
+    let mut globals: Vec<wasmparser::Global> = Vec::new();
+    let mut exports: Vec<wasmparser::Export> = Vec::new();
+    let mut passthrough: Vec<(u8, std::ops::Range<usize>)> = Vec::new();
+
     for payload in Parser::new(0).parse_all(wasm) {
         let payload = payload.map_err(|e| e.to_string())?;
-        if let Payload::CodeSectionEntry(body) = payload {
-            let mut ops = body.get_operators_reader().map_err(|e| e.to_string())?;
-            while !ops.eof() {
-                let op = ops.read().map_err(|e| e.to_string())?;
-                if is_float_op(&op) {
-                    return Err(
-                        "floating-point instructions are not allowed under strict Soroban compatibility"
-                            .to_string(),
-                    );
+        match payload {
+            Payload::TypeSection(reader) => {
+                for group in reader.into_iter_err_on_gc_types() {
+                    types.push(group.map_err(|e| e.to_string())?);
                 }
             }
+            Payload::ImportSection(reader) => {
+                for import in reader {
+                    imports.push(import.map_err(|e| e.to_string())?);
+                }
+            }
+            Payload::FunctionSection(reader) => {
+                for idx in reader {
+                    func_type_indices.push(idx.map_err(|e| e.to_string())?);
+                }
+            }
+            Payload::CodeSectionEntry(body) => code_bodies.push(body),
+            Payload::GlobalSection(reader) => {
+                for g in reader {
+                    globals.push(g.map_err(|e| e.to_string())?);
+                }
+            }
+            Payload::ExportSection(reader) => {
+                for e in reader {
+                    exports.push(e.map_err(|e| e.to_string())?);
+                }
+            }
+            Payload::StartSection { .. } => {
+                return Err("lower_soft_float: start sections are not supported yet".to_string());
+            }
+            Payload::ElementSection(_) => {
+                return Err(
+                    "lower_soft_float: element segments are not supported yet".to_string()
+                );
+            }
+            Payload::MemorySection(reader) => passthrough.push((0x05, reader.range())),
+            Payload::TableSection(reader) => passthrough.push((0x04, reader.range())),
+            Payload::DataSection(reader) => passthrough.push((0x0b, reader.range())),
+            Payload::DataCountSection { range, .. } => passthrough.push((0x0c, range)),
+            _ => {}
+        }
+    }
+
+    let n_imported_funcs = imports
+        .iter()
+        .filter(|i| matches!(i.ty, TypeRef::Func(_)))
+        .count() as u32;
+
+    // Collect the distinct helpers this module actually needs, in first-seen
+    // order, so the generated import section only pulls in what's used.
+    let mut helper_order: Vec<&'static SoftFloatHelper> = Vec::new();
+    let mut helper_index: HashMap<&'static str, u32> = HashMap::new();
+    for body in &code_bodies {
+        let mut ops = body.get_operators_reader().map_err(|e| e.to_string())?;
+        while !ops.eof() {
+            let op = ops.read().map_err(|e| e.to_string())?;
+            match lower_operator(&op) {
+                Some(Lowering::Helper(h)) => {
+                    helper_index.entry(h.name).or_insert_with(|| {
+                        helper_order.push(h);
+                        n_imported_funcs + helper_order.len() as u32 - 1
+                    });
+                }
+                Some(Lowering::Unsupported) => {
+                    return Err(format!(
+                        "lower_soft_float: opcode {op:?} has no software-float lowering yet"
+                    ));
+                }
+                _ => {}
+            }
+        }
+    }
+    let n_helpers = helper_order.len() as u32;
+
+    // Every function-index reference to an originally-defined function
+    // (index >= n_imported_funcs) shifts by n_helpers once the helper
+    // imports are spliced in ahead of the defined functions.
+    let shift_func_index = |idx: u32| -> u32 {
+        if idx < n_imported_funcs {
+            idx
+        } else {
+            idx + n_helpers
+        }
+    };
+
+    let mut module = Module::new();
+
+    // --- types: original types with float params/results scalarized,
+    // followed by one type per helper. ---
+    let mut type_section = wasm_encoder::TypeSection::new();
+    for ty in &types {
+        let params = ty.params().iter().map(scalarize_val_type);
+        let results = ty.results().iter().map(scalarize_val_type);
+        type_section.ty().function(params, results);
+    }
+    let helper_type_base = types.len() as u32;
+    for h in &helper_order {
+        type_section.ty().function(h.params.iter().copied(), h.results.iter().copied());
+    }
+    module.section(&type_section);
+
+    // --- imports: originals (retyped), then the helpers. ---
+    let mut import_section = wasm_encoder::ImportSection::new();
+    for import in &imports {
+        let ty = match import.ty {
+            TypeRef::Func(idx) => wasm_encoder::EntityType::Function(idx),
+            TypeRef::Memory(m) => wasm_encoder::EntityType::Memory(conv_memory_type(m)),
+            TypeRef::Table(t) => wasm_encoder::EntityType::Table(conv_table_type(t)),
+            TypeRef::Global(g) => wasm_encoder::EntityType::Global(wasm_encoder::GlobalType {
+                val_type: scalarize_val_type(&g.content_type),
+                mutable: g.mutable,
+                shared: false,
+            }),
+            TypeRef::Tag(_) => {
+                return Err("lower_soft_float: tag imports are not supported yet".to_string())
+            }
+        };
+        import_section.import(import.module, import.name, ty);
+    }
+    for h in &helper_order {
+        let ty_idx = helper_type_base + helper_index[h.name] - n_imported_funcs;
+        import_section.import(
+            SOFT_FLOAT_IMPORT_MODULE,
+            h.name,
+            wasm_encoder::EntityType::Function(ty_idx),
+        );
+    }
+    module.section(&import_section);
+
+    // --- functions ---
+    let mut function_section = wasm_encoder::FunctionSection::new();
+    for idx in &func_type_indices {
+        function_section.function(*idx);
+    }
+    module.section(&function_section);
+
+    for (id, range) in &passthrough {
+        if *id == 0x04 {
+            module.section(&wasm_encoder::RawSection {
+                id: *id,
+                data: &wasm[range.clone()],
+            });
+        }
+    }
+
+    // Section ids must appear in strictly increasing order: memory (0x05)
+    // comes before global (0x06) in the module, so it has to be emitted here
+    // even though we build the global section's content right below.
+    for (id, range) in &passthrough {
+        if *id == 0x05 {
+            module.section(&wasm_encoder::RawSection {
+                id: *id,
+                data: &wasm[range.clone()],
+            });
+        }
+    }
+
+    // --- globals ---
+    let mut global_section = wasm_encoder::GlobalSection::new();
+    for g in &globals {
+        let val_type = scalarize_val_type(&g.ty.content_type);
+        let init = scalarize_const_expr(&g.init_expr)?;
+        global_section.global(
+            wasm_encoder::GlobalType {
+                val_type,
+                mutable: g.ty.mutable,
+                shared: false,
+            },
+            &init,
+        );
+    }
+    module.section(&global_section);
+
+    // --- exports ---
+    let mut export_section = wasm_encoder::ExportSection::new();
+    for e in &exports {
+        let index = match e.kind {
+            ExternalKind::Func => shift_func_index(e.index),
+            _ => e.index,
+        };
+        export_section.export(e.name, conv_export_kind(e.kind), index);
+    }
+    module.section(&export_section);
+
+    for (id, range) in &passthrough {
+        if *id == 0x0c {
+            module.section(&wasm_encoder::RawSection {
+                id: *id,
+                data: &wasm[range.clone()],
+            });
+        }
+    }
+
+    // --- code ---
+    let mut code_section = wasm_encoder::CodeSection::new();
+    for body in &code_bodies {
+        let locals = body.get_locals_reader().map_err(|e| e.to_string())?;
+        let mut scalarized_locals = Vec::new();
+        for local in locals {
+            let (count, ty) = local.map_err(|e| e.to_string())?;
+            scalarized_locals.push((count, scalarize_val_type(&ty)));
+        }
+        let mut func = Function::new(scalarized_locals);
+
+        let mut ops = body.get_operators_reader().map_err(|e| e.to_string())?;
+        while !ops.eof() {
+            let op = ops.read().map_err(|e| e.to_string())?;
+            emit_lowered(&op, &helper_index, &shift_func_index, &mut func)?;
+        }
+        code_section.function(&func);
+    }
+    module.section(&code_section);
+
+    for (id, range) in &passthrough {
+        if *id == 0x0b {
+            module.section(&wasm_encoder::RawSection {
+                id: *id,
+                data: &wasm[range.clone()],
+            });
+        }
+    }
+
+    Ok(module.finish())
+}
+
+fn scalarize_val_type(ty: &wasmparser::ValType) -> EncValType {
+    match ty {
+        wasmparser::ValType::F32 => I32,
+        wasmparser::ValType::F64 => I64,
+        wasmparser::ValType::I32 => EncValType::I32,
+        wasmparser::ValType::I64 => EncValType::I64,
+        wasmparser::ValType::V128 => EncValType::V128,
+        wasmparser::ValType::Ref(r) => EncValType::Ref(conv_ref_type(r)),
+    }
+}
+
+fn conv_ref_type(r: &wasmparser::RefType) -> wasm_encoder::RefType {
+    wasm_encoder::RefType {
+        nullable: r.is_nullable(),
+        heap_type: conv_heap_type(r.heap_type()),
+    }
+}
+
+fn conv_heap_type(h: wasmparser::HeapType) -> wasm_encoder::HeapType {
+    match h {
+        wasmparser::HeapType::Abstract { shared, ty } => wasm_encoder::HeapType::Abstract {
+            shared,
+            ty: match ty {
+                wasmparser::AbstractHeapType::Func => wasm_encoder::AbstractHeapType::Func,
+                wasmparser::AbstractHeapType::Extern => wasm_encoder::AbstractHeapType::Extern,
+                _ => wasm_encoder::AbstractHeapType::Any,
+            },
+        },
+        wasmparser::HeapType::Concrete(idx) => {
+            wasm_encoder::HeapType::Concrete(idx.as_module_index().unwrap_or(0))
+        }
+    }
+}
+
+fn conv_memory_type(m: wasmparser::MemoryType) -> wasm_encoder::MemoryType {
+    wasm_encoder::MemoryType {
+        minimum: m.initial,
+        maximum: m.maximum,
+        memory64: m.memory64,
+        shared: m.shared,
+        page_size_log2: m.page_size_log2,
+    }
+}
+
+fn conv_table_type(t: wasmparser::TableType) -> wasm_encoder::TableType {
+    wasm_encoder::TableType {
+        element_type: conv_ref_type(&t.element_type),
+        minimum: t.initial,
+        maximum: t.maximum,
+        table64: t.table64,
+        shared: t.shared,
+    }
+}
+
+fn conv_export_kind(k: wasmparser::ExternalKind) -> wasm_encoder::ExportKind {
+    match k {
+        wasmparser::ExternalKind::Func => wasm_encoder::ExportKind::Func,
+        wasmparser::ExternalKind::Table => wasm_encoder::ExportKind::Table,
+        wasmparser::ExternalKind::Memory => wasm_encoder::ExportKind::Memory,
+        wasmparser::ExternalKind::Global => wasm_encoder::ExportKind::Global,
+        wasmparser::ExternalKind::Tag => wasm_encoder::ExportKind::Tag,
+    }
+}
+
+fn conv_memarg(m: wasmparser::MemArg) -> wasm_encoder::MemArg {
+    wasm_encoder::MemArg {
+        offset: m.offset,
+        align: m.align as u32,
+        memory_index: m.memory,
+    }
+}
+
+/// Rewrites a constant global initializer whose only instruction is an
+/// `f32.const`/`f64.const` into the matching `i32.const`/`i64.const` of the
+/// same bit pattern. Anything more exotic, including the multi-instruction
+/// extended-const sequences (e.g. `i32.const 1; i32.const 2; i32.add`) that
+/// the `extended-const` proposal legalizes, is out of scope for this pass
+/// and rejected rather than silently truncated.
+fn scalarize_const_expr(expr: &wasmparser::ConstExpr) -> Result<wasm_encoder::ConstExpr, String> {
+    let mut reader = expr.get_operators_reader();
+    let op = reader.read().map_err(|e| e.to_string())?;
+    let out = match op {
+        Operator::F32Const { value } => wasm_encoder::ConstExpr::i32_const(value.bits() as i32),
+        Operator::F64Const { value } => wasm_encoder::ConstExpr::i64_const(value.bits() as i64),
+        Operator::I32Const { value } => wasm_encoder::ConstExpr::i32_const(value),
+        Operator::I64Const { value } => wasm_encoder::ConstExpr::i64_const(value),
+        Operator::GlobalGet { global_index } => wasm_encoder::ConstExpr::global_get(global_index),
+        other => {
+            return Err(format!(
+                "lower_soft_float: unsupported global initializer {other:?}"
+            ))
+        }
+    };
+    match reader.read().map_err(|e| e.to_string())? {
+        Operator::End => {}
+        other => {
+            return Err(format!(
+                "lower_soft_float: unsupported multi-instruction global initializer \
+                 (opcode {other:?} follows {op:?})"
+            ))
+        }
+    }
+    Ok(out)
+}
+
+fn emit_lowered(
+    op: &Operator,
+    helper_index: &HashMap<&'static str, u32>,
+    shift_func_index: &impl Fn(u32) -> u32,
+    func: &mut Function,
+) -> Result<(), String> {
+    match lower_operator(op) {
+        Some(Lowering::ConstI32(v)) => {
+            func.instruction(&Instruction::I32Const(v));
+        }
+        Some(Lowering::ConstI64(v)) => {
+            func.instruction(&Instruction::I64Const(v));
+        }
+        Some(Lowering::Identity) => {}
+        Some(Lowering::Load32(m)) => {
+            func.instruction(&Instruction::I32Load(conv_memarg(m)));
+        }
+        Some(Lowering::Load64(m)) => {
+            func.instruction(&Instruction::I64Load(conv_memarg(m)));
+        }
+        Some(Lowering::Store32(m)) => {
+            func.instruction(&Instruction::I32Store(conv_memarg(m)));
+        }
+        Some(Lowering::Store64(m)) => {
+            func.instruction(&Instruction::I64Store(conv_memarg(m)));
+        }
+        Some(Lowering::Helper(h)) => {
+            func.instruction(&Instruction::Call(helper_index[h.name]));
+        }
+        Some(Lowering::Unsupported) => {
+            return Err(format!(
+                "lower_soft_float: opcode {op:?} has no software-float lowering yet"
+            ));
+        }
+        None => {
+            emit_passthrough(op, shift_func_index, func)?;
         }
     }
     Ok(())
 }
 
-fn is_float_op(op: &Operator) -> bool {
-    // Many of the `Operator` variants are prefixed with `F32` or `F64` when
-    // they perform floating-point operations. To avoid having to keep an
-    // exhaustive list in sync with whatever version of `wasmparser` is pulled
-    // in, simply look at the debug representation and check for the prefix.
-    //
-    // This is slightly less strict than matching individual variants, but it's
-    // good enough for our compatibility check: any float-related opcode will
-    // trigger the `starts_with` condition.
-    let name = format!("{:?}", op);
-    name.starts_with("F32") || name.starts_with("F64")
+/// Re-emits a non-float instruction as-is, adjusting the one piece of state
+/// that moved: function indices, for `call`/`return_call`/`ref.func`, shift
+/// by the number of helper imports spliced in ahead of the defined
+/// functions.
+///
+/// Only the opcodes a Soroban contract realistically emits (control flow,
+/// locals/globals, non-float memory access, and i32/i64 arithmetic) are
+/// covered; anything else is rejected so a gap here fails loudly instead of
+/// silently dropping an instruction.
+fn emit_passthrough(
+    op: &Operator,
+    shift_func_index: &impl Fn(u32) -> u32,
+    func: &mut Function,
+) -> Result<(), String> {
+    use Operator as O;
+    if let O::BrTable { targets } = op {
+        let default = targets.default();
+        let depths = targets
+            .targets()
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+        func.instruction(&Instruction::BrTable(depths.into(), default));
+        return Ok(());
+    }
+    let ins = match *op {
+        O::Call { function_index } => Instruction::Call(shift_func_index(function_index)),
+        O::ReturnCall { function_index } => {
+            Instruction::ReturnCall(shift_func_index(function_index))
+        }
+        O::RefFunc { function_index } => Instruction::RefFunc(shift_func_index(function_index)),
+        O::CallIndirect {
+            type_index,
+            table_index,
+            ..
+        } => Instruction::CallIndirect {
+            type_index,
+            table_index,
+        },
+
+        O::Unreachable => Instruction::Unreachable,
+        O::Nop => Instruction::Nop,
+        O::Block { blockty } => Instruction::Block(conv_blockty(blockty)),
+        O::Loop { blockty } => Instruction::Loop(conv_blockty(blockty)),
+        O::If { blockty } => Instruction::If(conv_blockty(blockty)),
+        O::Else => Instruction::Else,
+        O::End => Instruction::End,
+        O::Br { relative_depth } => Instruction::Br(relative_depth),
+        O::BrIf { relative_depth } => Instruction::BrIf(relative_depth),
+        O::Return => Instruction::Return,
+        O::Drop => Instruction::Drop,
+        O::Select => Instruction::Select,
+
+        O::LocalGet { local_index } => Instruction::LocalGet(local_index),
+        O::LocalSet { local_index } => Instruction::LocalSet(local_index),
+        O::LocalTee { local_index } => Instruction::LocalTee(local_index),
+        O::GlobalGet { global_index } => Instruction::GlobalGet(global_index),
+        O::GlobalSet { global_index } => Instruction::GlobalSet(global_index),
+
+        O::I32Load { memarg } => Instruction::I32Load(conv_memarg(memarg)),
+        O::I64Load { memarg } => Instruction::I64Load(conv_memarg(memarg)),
+        O::I32Load8S { memarg } => Instruction::I32Load8S(conv_memarg(memarg)),
+        O::I32Load8U { memarg } => Instruction::I32Load8U(conv_memarg(memarg)),
+        O::I32Load16S { memarg } => Instruction::I32Load16S(conv_memarg(memarg)),
+        O::I32Load16U { memarg } => Instruction::I32Load16U(conv_memarg(memarg)),
+        O::I64Load8S { memarg } => Instruction::I64Load8S(conv_memarg(memarg)),
+        O::I64Load8U { memarg } => Instruction::I64Load8U(conv_memarg(memarg)),
+        O::I64Load16S { memarg } => Instruction::I64Load16S(conv_memarg(memarg)),
+        O::I64Load16U { memarg } => Instruction::I64Load16U(conv_memarg(memarg)),
+        O::I64Load32S { memarg } => Instruction::I64Load32S(conv_memarg(memarg)),
+        O::I64Load32U { memarg } => Instruction::I64Load32U(conv_memarg(memarg)),
+        O::I32Store { memarg } => Instruction::I32Store(conv_memarg(memarg)),
+        O::I64Store { memarg } => Instruction::I64Store(conv_memarg(memarg)),
+        O::I32Store8 { memarg } => Instruction::I32Store8(conv_memarg(memarg)),
+        O::I32Store16 { memarg } => Instruction::I32Store16(conv_memarg(memarg)),
+        O::I64Store8 { memarg } => Instruction::I64Store8(conv_memarg(memarg)),
+        O::I64Store16 { memarg } => Instruction::I64Store16(conv_memarg(memarg)),
+        O::I64Store32 { memarg } => Instruction::I64Store32(conv_memarg(memarg)),
+        O::MemorySize { mem, .. } => Instruction::MemorySize(mem),
+        O::MemoryGrow { mem, .. } => Instruction::MemoryGrow(mem),
+
+        O::I32Const { value } => Instruction::I32Const(value),
+        O::I64Const { value } => Instruction::I64Const(value),
+
+        O::I32Eqz => Instruction::I32Eqz,
+        O::I32Eq => Instruction::I32Eq,
+        O::I32Ne => Instruction::I32Ne,
+        O::I32LtS => Instruction::I32LtS,
+        O::I32LtU => Instruction::I32LtU,
+        O::I32GtS => Instruction::I32GtS,
+        O::I32GtU => Instruction::I32GtU,
+        O::I32LeS => Instruction::I32LeS,
+        O::I32LeU => Instruction::I32LeU,
+        O::I32GeS => Instruction::I32GeS,
+        O::I32GeU => Instruction::I32GeU,
+        O::I64Eqz => Instruction::I64Eqz,
+        O::I64Eq => Instruction::I64Eq,
+        O::I64Ne => Instruction::I64Ne,
+        O::I64LtS => Instruction::I64LtS,
+        O::I64LtU => Instruction::I64LtU,
+        O::I64GtS => Instruction::I64GtS,
+        O::I64GtU => Instruction::I64GtU,
+        O::I64LeS => Instruction::I64LeS,
+        O::I64LeU => Instruction::I64LeU,
+        O::I64GeS => Instruction::I64GeS,
+        O::I64GeU => Instruction::I64GeU,
+
+        O::I32Clz => Instruction::I32Clz,
+        O::I32Ctz => Instruction::I32Ctz,
+        O::I32Popcnt => Instruction::I32Popcnt,
+        O::I32Add => Instruction::I32Add,
+        O::I32Sub => Instruction::I32Sub,
+        O::I32Mul => Instruction::I32Mul,
+        O::I32DivS => Instruction::I32DivS,
+        O::I32DivU => Instruction::I32DivU,
+        O::I32RemS => Instruction::I32RemS,
+        O::I32RemU => Instruction::I32RemU,
+        O::I32And => Instruction::I32And,
+        O::I32Or => Instruction::I32Or,
+        O::I32Xor => Instruction::I32Xor,
+        O::I32Shl => Instruction::I32Shl,
+        O::I32ShrS => Instruction::I32ShrS,
+        O::I32ShrU => Instruction::I32ShrU,
+        O::I32Rotl => Instruction::I32Rotl,
+        O::I32Rotr => Instruction::I32Rotr,
+
+        O::I64Clz => Instruction::I64Clz,
+        O::I64Ctz => Instruction::I64Ctz,
+        O::I64Popcnt => Instruction::I64Popcnt,
+        O::I64Add => Instruction::I64Add,
+        O::I64Sub => Instruction::I64Sub,
+        O::I64Mul => Instruction::I64Mul,
+        O::I64DivS => Instruction::I64DivS,
+        O::I64DivU => Instruction::I64DivU,
+        O::I64RemS => Instruction::I64RemS,
+        O::I64RemU => Instruction::I64RemU,
+        O::I64And => Instruction::I64And,
+        O::I64Or => Instruction::I64Or,
+        O::I64Xor => Instruction::I64Xor,
+        O::I64Shl => Instruction::I64Shl,
+        O::I64ShrS => Instruction::I64ShrS,
+        O::I64ShrU => Instruction::I64ShrU,
+        O::I64Rotl => Instruction::I64Rotl,
+        O::I64Rotr => Instruction::I64Rotr,
+
+        O::I32WrapI64 => Instruction::I32WrapI64,
+        O::I64ExtendI32S => Instruction::I64ExtendI32S,
+        O::I64ExtendI32U => Instruction::I64ExtendI32U,
+        O::I32Extend8S => Instruction::I32Extend8S,
+        O::I32Extend16S => Instruction::I32Extend16S,
+        O::I64Extend8S => Instruction::I64Extend8S,
+        O::I64Extend16S => Instruction::I64Extend16S,
+        O::I64Extend32S => Instruction::I64Extend32S,
+
+        _ => return Err(format!("lower_soft_float: unsupported opcode {op:?}")),
+    };
+    func.instruction(&ins);
+    Ok(())
+}
+
+fn conv_blockty(ty: wasmparser::BlockType) -> wasm_encoder::BlockType {
+    match ty {
+        wasmparser::BlockType::Empty => wasm_encoder::BlockType::Empty,
+        wasmparser::BlockType::Type(t) => wasm_encoder::BlockType::Result(scalarize_val_type(&t)),
+        wasmparser::BlockType::FuncType(idx) => wasm_encoder::BlockType::FunctionType(idx),
+    }
+}
+
+/// One class of WASM feature that a deterministic smart-contract sandbox
+/// (Soroban's on-chain VM being the motivating example) may refuse to run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    /// Native float ops, float-touching conversions, and SIMD float lanes —
+    /// see [`is_float_op`].
+    Float,
+    /// Any `v128` opcode or value, covering both the integer and float SIMD
+    /// lanes.
+    Simd,
+    /// Shared-memory atomics (`memory.atomic.*`, `*.atomic.rmw*`,
+    /// `atomic.fence`) and shared linear memories.
+    Atomics,
+    /// `memory.copy`/`memory.fill`/`memory.init`/`data.drop` and their table
+    /// counterparts.
+    BulkMemory,
+    /// `ref.null`/`ref.func`/`ref.is_null`/`table.get`/`table.set`.
+    ReferenceTypes,
+    /// `return_call`/`return_call_indirect`.
+    TailCalls,
+    /// The exception-handling proposal, legacy (`try`/`catch`/`rethrow`/
+    /// `delegate`) and current (`throw`/`throw_ref`/`try_table`) forms.
+    Exceptions,
+    /// The module itself could not be parsed; not gated by [`Policy`], this
+    /// is always reported.
+    Malformed,
+}
+
+impl Category {
+    /// A short plural noun phrase describing this category, used to build
+    /// violation messages, e.g. "floating-point instructions".
+    fn description(self) -> &'static str {
+        match self {
+            Category::Float => "floating-point instructions",
+            Category::Simd => "SIMD instructions",
+            Category::Atomics => "atomic instructions",
+            Category::BulkMemory => "bulk-memory instructions",
+            Category::ReferenceTypes => "reference-type instructions",
+            Category::TailCalls => "tail-call instructions",
+            Category::Exceptions => "exception-handling instructions",
+            Category::Malformed => "a malformed module",
+        }
+    }
+}
+
+/// Per-category toggles for [`check`], analogous to how target features gate
+/// instruction availability: each field defaults to `false` ("not allowed"),
+/// and callers opt individual categories back in for the environments that
+/// support them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Policy {
+    pub allow_float: bool,
+    pub allow_simd: bool,
+    pub allow_atomics: bool,
+    pub allow_bulk_memory: bool,
+    pub allow_reference_types: bool,
+    pub allow_tail_calls: bool,
+    pub allow_exceptions: bool,
+}
+
+impl Policy {
+    /// The strict default: every restricted category is disallowed.
+    pub fn strict() -> Self {
+        Self::default()
+    }
+
+    /// Whether `category` is permitted under this policy. [`Category::Malformed`]
+    /// is never permitted, since it means the module couldn't be scanned at all.
+    fn allows(self, category: Category) -> bool {
+        match category {
+            Category::Float => self.allow_float,
+            Category::Simd => self.allow_simd,
+            Category::Atomics => self.allow_atomics,
+            Category::BulkMemory => self.allow_bulk_memory,
+            Category::ReferenceTypes => self.allow_reference_types,
+            Category::TailCalls => self.allow_tail_calls,
+            Category::Exceptions => self.allow_exceptions,
+            Category::Malformed => false,
+        }
+    }
+}
+
+/// A single instance of a module violating a [`Policy`].
+#[derive(Debug, Clone)]
+pub struct Violation {
+    pub category: Category,
+    /// Index of the function the violation occurred in, in the module's
+    /// function index space. `None` for module-level violations (e.g. a
+    /// shared memory) that aren't attributable to one function.
+    pub func_index: Option<u32>,
+    /// Byte offset of the opcode within the code section. `None` for
+    /// module-level violations.
+    pub offset: Option<usize>,
+    /// Set only for [`Category::Malformed`]: the underlying parser error.
+    pub message: Option<String>,
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(message) = &self.message {
+            return write!(f, "{message}");
+        }
+        write!(f, "{} are not allowed", self.category.description())?;
+        if let Some(func_index) = self.func_index {
+            write!(f, " (function #{func_index}")?;
+            if let Some(offset) = self.offset {
+                write!(f, ", offset {offset}")?;
+            }
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+/// Every [`Category`] that `op` participates in. Most opcodes participate in
+/// none; SIMD float lanes participate in both `Float` and `Simd`.
+fn categories_for(op: &Operator) -> Vec<Category> {
+    let mut categories = Vec::new();
+
+    if is_float_op(op) {
+        categories.push(Category::Float);
+    }
+
+    if is_simd_op(op) {
+        categories.push(Category::Simd);
+    }
+
+    let name = format!("{op:?}");
+    if name.contains("Atomic") {
+        categories.push(Category::Atomics);
+    }
+
+    if matches!(
+        op,
+        Operator::MemoryCopy { .. }
+            | Operator::MemoryFill { .. }
+            | Operator::MemoryInit { .. }
+            | Operator::DataDrop { .. }
+            | Operator::TableCopy { .. }
+            | Operator::TableInit { .. }
+    ) {
+        categories.push(Category::BulkMemory);
+    }
+
+    if matches!(
+        op,
+        Operator::RefNull { .. }
+            | Operator::RefFunc { .. }
+            | Operator::RefIsNull
+            | Operator::TableGet { .. }
+            | Operator::TableSet { .. }
+    ) {
+        categories.push(Category::ReferenceTypes);
+    }
+
+    if matches!(
+        op,
+        Operator::ReturnCall { .. } | Operator::ReturnCallIndirect { .. }
+    ) {
+        categories.push(Category::TailCalls);
+    }
+
+    if matches!(
+        op,
+        Operator::Try { .. }
+            | Operator::Catch { .. }
+            | Operator::CatchAll
+            | Operator::Rethrow { .. }
+            | Operator::Delegate { .. }
+            | Operator::Throw { .. }
+            | Operator::ThrowRef
+            | Operator::TryTable { .. }
+    ) {
+        categories.push(Category::Exceptions);
+    }
+
+    categories
+}
+
+/// Scans every function body in `wasm` against `policy` and returns every
+/// violation found, rather than bailing out on the first one, so callers get
+/// a complete compatibility report in one pass.
+///
+/// Also flags shared linear memories under [`Category::Atomics`], since a
+/// shared memory is how the atomics proposal opts a module into the
+/// shared-memory threading model in the first place.
+///
+/// And flags any function whose signature or declared locals carry a
+/// `v128` value under [`Category::Simd`], even if its body never executes a
+/// `V128*`/`*x4`/`*x2` opcode — a `(v128) -> ()` parameter that's
+/// immediately dropped is still forbidden SIMD surface, mirroring the
+/// [`Policy::allows_val_type`] check [`validate_exports`] already does for
+/// exports.
+pub fn check(wasm: &[u8], policy: &Policy) -> Result<(), Vec<Violation>> {
+    let mut violations = Vec::new();
+    let mut n_imported_funcs = 0u32;
+    let mut next_func_index = 0u32;
+    let mut types: Vec<wasmparser::FuncType> = Vec::new();
+    // Indexed by function index (imports first, then defined functions),
+    // giving the index into `types` for that function's signature.
+    let mut func_type_indices: Vec<u32> = Vec::new();
+    // Start of the code section's contents in `wasm`, so opcode offsets can
+    // be reported relative to the code section.
+    let mut code_section_start = 0usize;
+
+    macro_rules! bail_malformed {
+        ($err:expr) => {{
+            violations.push(Violation {
+                category: Category::Malformed,
+                func_index: None,
+                offset: None,
+                message: Some($err.to_string()),
+            });
+            return Err(violations);
+        }};
+    }
+
+    for payload in Parser::new(0).parse_all(wasm) {
+        let payload = match payload {
+            Ok(payload) => payload,
+            Err(e) => bail_malformed!(e),
+        };
+        match payload {
+            Payload::TypeSection(reader) => {
+                for group in reader.into_iter_err_on_gc_types() {
+                    types.push(match group {
+                        Ok(group) => group,
+                        Err(e) => bail_malformed!(e),
+                    });
+                }
+            }
+            Payload::ImportSection(reader) => {
+                for import in reader {
+                    let import = match import {
+                        Ok(import) => import,
+                        Err(e) => bail_malformed!(e),
+                    };
+                    if let wasmparser::TypeRef::Func(type_idx) = import.ty {
+                        n_imported_funcs += 1;
+                        func_type_indices.push(type_idx);
+                    }
+                    if let wasmparser::TypeRef::Memory(memory) = import.ty {
+                        if memory.shared && !policy.allow_atomics {
+                            violations.push(Violation {
+                                category: Category::Atomics,
+                                func_index: None,
+                                offset: None,
+                                message: None,
+                            });
+                        }
+                    }
+                }
+                next_func_index = n_imported_funcs;
+            }
+            Payload::FunctionSection(reader) => {
+                for type_idx in reader {
+                    func_type_indices.push(match type_idx {
+                        Ok(type_idx) => type_idx,
+                        Err(e) => bail_malformed!(e),
+                    });
+                }
+            }
+            Payload::MemorySection(reader) if !policy.allow_atomics => {
+                for memory in reader {
+                    let memory = match memory {
+                        Ok(memory) => memory,
+                        Err(e) => bail_malformed!(e),
+                    };
+                    if memory.shared {
+                        violations.push(Violation {
+                            category: Category::Atomics,
+                            func_index: None,
+                            offset: None,
+                            message: None,
+                        });
+                    }
+                }
+            }
+            Payload::CodeSectionStart { range, .. } => code_section_start = range.start,
+            Payload::CodeSectionEntry(body) => {
+                let func_index = next_func_index;
+                next_func_index += 1;
+
+                if !policy.allows(Category::Simd) {
+                    let has_v128_signature = func_type_indices
+                        .get(func_index as usize)
+                        .and_then(|&type_idx| types.get(type_idx as usize))
+                        .is_some_and(|func_type| {
+                            func_type
+                                .params()
+                                .iter()
+                                .chain(func_type.results())
+                                .any(|ty| *ty == wasmparser::ValType::V128)
+                        });
+                    if has_v128_signature {
+                        violations.push(Violation {
+                            category: Category::Simd,
+                            func_index: Some(func_index),
+                            offset: None,
+                            message: None,
+                        });
+                    }
+
+                    let locals = match body.get_locals_reader() {
+                        Ok(locals) => locals,
+                        Err(e) => bail_malformed!(e),
+                    };
+                    for local in locals {
+                        let (_, ty) = match local {
+                            Ok(local) => local,
+                            Err(e) => bail_malformed!(e),
+                        };
+                        if ty == wasmparser::ValType::V128 {
+                            violations.push(Violation {
+                                category: Category::Simd,
+                                func_index: Some(func_index),
+                                offset: None,
+                                message: None,
+                            });
+                        }
+                    }
+                }
+
+                let mut ops = match body.get_operators_reader() {
+                    Ok(ops) => ops,
+                    Err(e) => bail_malformed!(e),
+                };
+                while !ops.eof() {
+                    let offset = ops.original_position() - code_section_start;
+                    let op = match ops.read() {
+                        Ok(op) => op,
+                        Err(e) => bail_malformed!(e),
+                    };
+                    for category in categories_for(&op) {
+                        if !policy.allows(category) {
+                            violations.push(Violation {
+                                category,
+                                func_index: Some(func_index),
+                                offset: Some(offset),
+                                message: None,
+                            });
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(violations)
+    }
+}
+
+impl Policy {
+    /// Whether a value of type `ty` may appear anywhere in a module under
+    /// this policy — used by [`validate_exports`] to flag entrypoints whose
+    /// signature uses a forbidden type (e.g. `f64`) even if the arity and
+    /// types otherwise match the expected ABI.
+    fn allows_val_type(self, ty: wasmparser::ValType) -> bool {
+        match ty {
+            wasmparser::ValType::F32 | wasmparser::ValType::F64 => self.allow_float,
+            wasmparser::ValType::V128 => self.allow_simd,
+            wasmparser::ValType::Ref(_) => self.allow_reference_types,
+            wasmparser::ValType::I32 | wasmparser::ValType::I64 => true,
+        }
+    }
+}
+
+/// The expected ABI of one contract entrypoint: the export name the host
+/// looks it up by, and the param/result types its function signature must
+/// match exactly.
+#[derive(Debug, Clone)]
+pub struct ExpectedExport {
+    pub name: String,
+    pub params: Vec<wasmparser::ValType>,
+    pub results: Vec<wasmparser::ValType>,
+}
+
+/// Validates that `wasm` exposes every entrypoint in `spec` under its
+/// conventional name with a matching signature, and that no entrypoint's
+/// signature uses a type `policy` forbids.
+///
+/// WASM requires caller/callee signatures to match exactly, and toolchains
+/// commonly mangle entry symbols rather than leaving behind a flexible
+/// C-style `main`; a contract whose exported arity or types don't line up
+/// with the host's calling convention fails silently on-chain rather than
+/// erroring at the call site. This surfaces every such mismatch — missing
+/// exports, arity mismatches, and type mismatches — in one pass instead of
+/// letting the first one hide the rest.
+pub fn validate_exports(
+    wasm: &[u8],
+    spec: &[ExpectedExport],
+    policy: &Policy,
+) -> Result<(), Vec<String>> {
+    let mut types: Vec<wasmparser::FuncType> = Vec::new();
+    // Indexed by function index (imports first, then defined functions),
+    // giving the index into `types` for that function's signature.
+    let mut func_type_indices: Vec<u32> = Vec::new();
+    let mut exports: Vec<wasmparser::Export> = Vec::new();
+
+    for payload in Parser::new(0).parse_all(wasm) {
+        let payload = payload.map_err(|e| vec![e.to_string()])?;
+        match payload {
+            Payload::TypeSection(reader) => {
+                for group in reader.into_iter_err_on_gc_types() {
+                    types.push(group.map_err(|e| vec![e.to_string()])?);
+                }
+            }
+            Payload::ImportSection(reader) => {
+                for import in reader {
+                    let import = import.map_err(|e| vec![e.to_string()])?;
+                    if let wasmparser::TypeRef::Func(type_idx) = import.ty {
+                        func_type_indices.push(type_idx);
+                    }
+                }
+            }
+            Payload::FunctionSection(reader) => {
+                for type_idx in reader {
+                    func_type_indices.push(type_idx.map_err(|e| vec![e.to_string()])?);
+                }
+            }
+            Payload::ExportSection(reader) => {
+                for export in reader {
+                    exports.push(export.map_err(|e| vec![e.to_string()])?);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut violations = Vec::new();
+
+    for expected in spec {
+        let Some(export) = exports
+            .iter()
+            .find(|e| e.kind == wasmparser::ExternalKind::Func && e.name == expected.name)
+        else {
+            violations.push(format!("missing export `{}`", expected.name));
+            continue;
+        };
+
+        let Some(ty) = func_type_indices
+            .get(export.index as usize)
+            .and_then(|&type_idx| types.get(type_idx as usize))
+        else {
+            violations.push(format!(
+                "export `{}` does not resolve to a valid function type",
+                expected.name
+            ));
+            continue;
+        };
+
+        for (position, actual) in ty.params().iter().chain(ty.results()).enumerate() {
+            if !policy.allows_val_type(*actual) {
+                violations.push(format!(
+                    "export `{}`: type #{position} ({actual:?}) is not allowed by policy",
+                    expected.name
+                ));
+            }
+        }
+
+        if ty.params().len() != expected.params.len() || ty.results().len() != expected.results.len()
+        {
+            violations.push(format!(
+                "export `{}` has signature ({:?}) -> ({:?}), expected ({:?}) -> ({:?})",
+                expected.name,
+                ty.params(),
+                ty.results(),
+                expected.params,
+                expected.results
+            ));
+            continue;
+        }
+
+        for (i, (actual, want)) in ty.params().iter().zip(&expected.params).enumerate() {
+            if actual != want {
+                violations.push(format!(
+                    "export `{}` param {i}: expected {want:?}, found {actual:?}",
+                    expected.name
+                ));
+            }
+        }
+        for (i, (actual, want)) in ty.results().iter().zip(&expected.results).enumerate() {
+            if actual != want {
+                violations.push(format!(
+                    "export `{}` result {i}: expected {want:?}, found {actual:?}",
+                    expected.name
+                ));
+            }
+        }
+    }
+
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(violations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_encoder::{CodeSection, Function, Instruction, Module, TypeSection};
+
+    /// Assembles a single-function module whose body is exactly `ops`, then
+    /// runs it through `enforce_soroban_compatibility`.
+    fn soroban_check(ops: &[Instruction]) -> Result<(), String> {
+        let mut module = Module::new();
+
+        let mut types = TypeSection::new();
+        types
+            .ty()
+            .function(Vec::<wasm_encoder::ValType>::new(), Vec::new());
+        module.section(&types);
+
+        let mut code = CodeSection::new();
+        let mut func = Function::new([]);
+        for op in ops {
+            func.instruction(op);
+        }
+        func.instruction(&Instruction::End);
+        code.function(&func);
+        module.section(&code);
+
+        enforce_soroban_compatibility(&module.finish())
+    }
+
+    #[test]
+    fn rejects_native_float_op() {
+        assert!(soroban_check(&[Instruction::F64Const(1.0), Instruction::Drop]).is_err());
+    }
+
+    #[test]
+    fn rejects_float_to_int_conversion() {
+        assert!(soroban_check(&[
+            Instruction::F32Const(1.0),
+            Instruction::I32TruncF32S,
+            Instruction::Drop
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn rejects_saturating_trunc() {
+        assert!(soroban_check(&[
+            Instruction::F64Const(1.0),
+            Instruction::I64TruncSatF64U,
+            Instruction::Drop
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn rejects_simd_float_lane_op() {
+        assert!(soroban_check(&[
+            Instruction::V128Const(0i128),
+            Instruction::F32x4Sqrt,
+            Instruction::Drop
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn rejects_simd_int_to_float_convert() {
+        assert!(soroban_check(&[
+            Instruction::V128Const(0i128),
+            Instruction::F32x4ConvertI32x4S,
+            Instruction::Drop
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn accepts_pure_integer_body() {
+        assert!(soroban_check(&[
+            Instruction::I32Const(1),
+            Instruction::I32Const(2),
+            Instruction::I32Add,
+            Instruction::Drop
+        ])
+        .is_ok());
+    }
+
+    /// Builds a module exporting a function that adds two `f64` params and
+    /// runs it through `lower_soft_float`, asserting the result is float-free
+    /// and still well-formed enough for `wasmparser` to validate.
+    #[test]
+    fn lower_soft_float_strips_all_float_opcodes() {
+        let mut module = Module::new();
+
+        let mut types = TypeSection::new();
+        types
+            .ty()
+            .function([wasm_encoder::ValType::F64, wasm_encoder::ValType::F64], [
+                wasm_encoder::ValType::F64,
+            ]);
+        module.section(&types);
+
+        let mut functions = wasm_encoder::FunctionSection::new();
+        functions.function(0);
+        module.section(&functions);
+
+        let mut exports = wasm_encoder::ExportSection::new();
+        exports.export("add", wasm_encoder::ExportKind::Func, 0);
+        module.section(&exports);
+
+        let mut code = CodeSection::new();
+        let mut func = Function::new([]);
+        func.instruction(&Instruction::LocalGet(0));
+        func.instruction(&Instruction::LocalGet(1));
+        func.instruction(&Instruction::F64Add);
+        func.instruction(&Instruction::End);
+        code.function(&func);
+        module.section(&code);
+
+        let lowered = lower_soft_float(&module.finish()).expect("lowering should succeed");
+
+        wasmparser::validate(&lowered).expect("lowered module should still be well-formed");
+        assert!(enforce_soroban_compatibility(&lowered).is_ok());
+    }
+
+    /// Most real Soroban/Rust-compiled contracts declare both a linear memory
+    /// and at least one global (e.g. a stack pointer) alongside any floats,
+    /// so the section ordering in the rewritten module needs to survive that
+    /// combination, not just the float-only case above.
+    #[test]
+    fn lower_soft_float_preserves_section_order_with_memory_and_global() {
+        let mut module = Module::new();
+
+        let mut types = TypeSection::new();
+        types.ty().function([], []);
+        module.section(&types);
+
+        let mut functions = wasm_encoder::FunctionSection::new();
+        functions.function(0);
+        module.section(&functions);
+
+        let mut memories = wasm_encoder::MemorySection::new();
+        memories.memory(wasm_encoder::MemoryType {
+            minimum: 1,
+            maximum: None,
+            memory64: false,
+            shared: false,
+            page_size_log2: None,
+        });
+        module.section(&memories);
+
+        let mut globals = wasm_encoder::GlobalSection::new();
+        globals.global(
+            wasm_encoder::GlobalType {
+                val_type: wasm_encoder::ValType::F64,
+                mutable: true,
+                shared: false,
+            },
+            &wasm_encoder::ConstExpr::f64_const(0.0),
+        );
+        module.section(&globals);
+
+        let mut code = CodeSection::new();
+        let mut func = Function::new([]);
+        func.instruction(&Instruction::End);
+        code.function(&func);
+        module.section(&code);
+
+        let lowered = lower_soft_float(&module.finish()).expect("lowering should succeed");
+
+        wasmparser::validate(&lowered).expect("lowered module should still be well-formed");
+        assert!(enforce_soroban_compatibility(&lowered).is_ok());
+    }
+
+    /// The `extended-const` proposal legalizes multi-instruction global
+    /// initializers like `i32.const 1; i32.const 2; i32.add`. `lower_soft_float`
+    /// only understands single-instruction initializers, so it must reject
+    /// this rather than silently keeping just the first instruction (which
+    /// would lower the global to the wrong value, 1 instead of 3).
+    #[test]
+    fn lower_soft_float_rejects_multi_instruction_global_initializer() {
+        let mut module = Module::new();
+
+        let mut types = TypeSection::new();
+        types.ty().function([], []);
+        module.section(&types);
+
+        let mut functions = wasm_encoder::FunctionSection::new();
+        functions.function(0);
+        module.section(&functions);
+
+        let mut globals = wasm_encoder::GlobalSection::new();
+        globals.global(
+            wasm_encoder::GlobalType {
+                val_type: wasm_encoder::ValType::I32,
+                mutable: false,
+                shared: false,
+            },
+            &wasm_encoder::ConstExpr::extended([
+                Instruction::I32Const(1),
+                Instruction::I32Const(2),
+                Instruction::I32Add,
+            ]),
+        );
+        module.section(&globals);
+
+        let mut code = CodeSection::new();
+        let mut func = Function::new([]);
+        func.instruction(&Instruction::End);
+        code.function(&func);
+        module.section(&code);
+
+        let wasm = module.finish();
+        assert!(wasmparser::validate(&wasm).is_ok());
+
+        let err = lower_soft_float(&wasm).expect_err(
+            "lowering a multi-instruction global initializer should fail loudly, \
+             not silently keep only the first instruction",
+        );
+        assert!(err.contains("unsupported multi-instruction global initializer"));
+    }
+
+    /// Builds a module with a `v128`-returning function (no floats), for the
+    /// tests below to check against a policy.
+    fn simd_only_module() -> Vec<u8> {
+        let mut module = Module::new();
+
+        let mut types = TypeSection::new();
+        types
+            .ty()
+            .function([], [wasm_encoder::ValType::V128]);
+        module.section(&types);
+
+        let mut functions = wasm_encoder::FunctionSection::new();
+        functions.function(0);
+        module.section(&functions);
+
+        let mut code = CodeSection::new();
+        let mut func = Function::new([]);
+        func.instruction(&Instruction::V128Const(0));
+        func.instruction(&Instruction::End);
+        code.function(&func);
+        module.section(&code);
+
+        module.finish()
+    }
+
+    #[test]
+    fn check_reports_simd_violation_under_strict_policy() {
+        // One violation for the `v128` result in the function's own
+        // signature, and one for the `v128.const` opcode in its body.
+        let violations = check(&simd_only_module(), &Policy::strict()).unwrap_err();
+        assert_eq!(violations.len(), 2);
+        assert!(violations.iter().all(|v| v.category == Category::Simd));
+        assert!(violations.iter().all(|v| v.func_index == Some(0)));
+    }
+
+    /// Builds a module with a `(v128) -> ()` function whose body never
+    /// executes a single SIMD opcode — the parameter is just dropped — so
+    /// the only forbidden SIMD surface is the function's own signature.
+    fn simd_signature_only_module() -> Vec<u8> {
+        let mut module = Module::new();
+
+        let mut types = TypeSection::new();
+        types
+            .ty()
+            .function([wasm_encoder::ValType::V128], []);
+        module.section(&types);
+
+        let mut functions = wasm_encoder::FunctionSection::new();
+        functions.function(0);
+        module.section(&functions);
+
+        let mut code = CodeSection::new();
+        let mut func = Function::new([]);
+        func.instruction(&Instruction::LocalGet(0));
+        func.instruction(&Instruction::Drop);
+        func.instruction(&Instruction::End);
+        code.function(&func);
+        module.section(&code);
+
+        module.finish()
+    }
+
+    #[test]
+    fn check_reports_simd_violation_for_v128_in_signature_with_no_simd_opcode() {
+        let violations = check(&simd_signature_only_module(), &Policy::strict()).unwrap_err();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].category, Category::Simd);
+        assert_eq!(violations[0].func_index, Some(0));
+    }
+
+    /// Builds a module whose only SIMD use is a pure-integer lane op
+    /// (`i8x16.splat`, fed by an `i32.const`) with no `v128` load/const and
+    /// no lane width that happens to contain "x4"/"x2" in its name.
+    fn integer_simd_only_module() -> Vec<u8> {
+        let mut module = Module::new();
+
+        let mut types = TypeSection::new();
+        types
+            .ty()
+            .function([], [wasm_encoder::ValType::V128]);
+        module.section(&types);
+
+        let mut functions = wasm_encoder::FunctionSection::new();
+        functions.function(0);
+        module.section(&functions);
+
+        let mut code = CodeSection::new();
+        let mut func = Function::new([]);
+        func.instruction(&Instruction::I32Const(1));
+        func.instruction(&Instruction::I8x16Splat);
+        func.instruction(&Instruction::End);
+        code.function(&func);
+        module.section(&code);
+
+        module.finish()
+    }
+
+    #[test]
+    fn check_reports_simd_violation_for_pure_integer_lane_op() {
+        // One violation for the `v128` result in the function's own
+        // signature, and one for the `i8x16.splat` opcode in its body.
+        let violations = check(&integer_simd_only_module(), &Policy::strict()).unwrap_err();
+        assert_eq!(violations.len(), 2);
+        assert!(violations.iter().all(|v| v.category == Category::Simd));
+    }
+
+    #[test]
+    fn check_allows_simd_when_policy_permits_it() {
+        let policy = Policy {
+            allow_simd: true,
+            ..Policy::strict()
+        };
+        assert!(check(&simd_only_module(), &policy).is_ok());
+    }
+
+    #[test]
+    fn check_collects_every_violation_in_one_pass() {
+        let mut module = Module::new();
+
+        let mut types = TypeSection::new();
+        types.ty().function([], []);
+        module.section(&types);
+
+        let mut functions = wasm_encoder::FunctionSection::new();
+        functions.function(0);
+        module.section(&functions);
+
+        let mut code = CodeSection::new();
+        let mut func = Function::new([]);
+        func.instruction(&Instruction::F64Const(1.0));
+        func.instruction(&Instruction::Drop);
+        func.instruction(&Instruction::V128Const(0));
+        func.instruction(&Instruction::Drop);
+        func.instruction(&Instruction::End);
+        code.function(&func);
+        module.section(&code);
+
+        let violations = check(&module.finish(), &Policy::strict()).unwrap_err();
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].category, Category::Float);
+        assert_eq!(violations[1].category, Category::Simd);
+    }
+
+    /// A module exporting `fn add(i32, i32) -> i32` under the name `add`.
+    fn module_with_add_export() -> Vec<u8> {
+        let mut module = Module::new();
+
+        let mut types = TypeSection::new();
+        types.ty().function(
+            [wasm_encoder::ValType::I32, wasm_encoder::ValType::I32],
+            [wasm_encoder::ValType::I32],
+        );
+        module.section(&types);
+
+        let mut functions = wasm_encoder::FunctionSection::new();
+        functions.function(0);
+        module.section(&functions);
+
+        let mut exports = wasm_encoder::ExportSection::new();
+        exports.export("add", wasm_encoder::ExportKind::Func, 0);
+        module.section(&exports);
+
+        let mut code = CodeSection::new();
+        let mut func = Function::new([]);
+        func.instruction(&Instruction::LocalGet(0));
+        func.instruction(&Instruction::LocalGet(1));
+        func.instruction(&Instruction::I32Add);
+        func.instruction(&Instruction::End);
+        code.function(&func);
+        module.section(&code);
+
+        module.finish()
+    }
+
+    #[test]
+    fn validate_exports_accepts_matching_signature() {
+        let spec = [ExpectedExport {
+            name: "add".to_string(),
+            params: vec![wasmparser::ValType::I32, wasmparser::ValType::I32],
+            results: vec![wasmparser::ValType::I32],
+        }];
+        assert!(validate_exports(&module_with_add_export(), &spec, &Policy::strict()).is_ok());
+    }
+
+    #[test]
+    fn validate_exports_reports_missing_export() {
+        let spec = [ExpectedExport {
+            name: "init".to_string(),
+            params: vec![],
+            results: vec![],
+        }];
+        let violations =
+            validate_exports(&module_with_add_export(), &spec, &Policy::strict()).unwrap_err();
+        assert_eq!(violations, vec!["missing export `init`".to_string()]);
+    }
+
+    #[test]
+    fn validate_exports_reports_arity_mismatch() {
+        let spec = [ExpectedExport {
+            name: "add".to_string(),
+            params: vec![wasmparser::ValType::I32],
+            results: vec![wasmparser::ValType::I32],
+        }];
+        let violations =
+            validate_exports(&module_with_add_export(), &spec, &Policy::strict()).unwrap_err();
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].contains("signature"));
+    }
+
+    #[test]
+    fn validate_exports_reports_forbidden_type_in_signature() {
+        let mut module = Module::new();
+
+        let mut types = TypeSection::new();
+        types
+            .ty()
+            .function([wasm_encoder::ValType::F64], [wasm_encoder::ValType::F64]);
+        module.section(&types);
+
+        let mut functions = wasm_encoder::FunctionSection::new();
+        functions.function(0);
+        module.section(&functions);
+
+        let mut exports = wasm_encoder::ExportSection::new();
+        exports.export("double", wasm_encoder::ExportKind::Func, 0);
+        module.section(&exports);
+
+        let mut code = CodeSection::new();
+        let mut func = Function::new([]);
+        func.instruction(&Instruction::LocalGet(0));
+        func.instruction(&Instruction::End);
+        code.function(&func);
+        module.section(&code);
+
+        let spec = [ExpectedExport {
+            name: "double".to_string(),
+            params: vec![wasmparser::ValType::F64],
+            results: vec![wasmparser::ValType::F64],
+        }];
+        let violations = validate_exports(&module.finish(), &spec, &Policy::strict()).unwrap_err();
+        assert!(violations
+            .iter()
+            .any(|v| v.contains("not allowed by policy")));
+    }
 }